@@ -5,7 +5,9 @@
 //!
 //! See [`StaticFiles`] for more details.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use include_dir::File;
 use rocket::fs::Options;
@@ -13,6 +15,7 @@ use rocket::http::ext::IntoOwned;
 use rocket::http::uri::fmt::Path;
 use rocket::http::uri::Segments;
 use rocket::http::ContentType;
+use rocket::http::Header;
 use rocket::http::Method;
 use rocket::http::Status;
 use rocket::outcome::IntoOutcome;
@@ -28,6 +31,240 @@ use rocket::Route;
 pub use include_dir::include_dir;
 pub use include_dir::Dir;
 
+#[cfg(feature = "compression")]
+pub use compression::Encodings;
+
+/// What a (possibly rewritten) request path currently resolves to inside the
+/// embedded directory.
+///
+/// A [`Rewriter`] inspects and replaces the [`Resolved`] held by a
+/// [`FileResolution`] as the request walks the rewrite chain.
+pub enum Resolved<'r> {
+    /// The path names an embedded [`File`].
+    File(&'r File<'r>),
+    /// The path names a directory.
+    Dir(&'r Dir<'r>),
+    /// The path names nothing in the embedded directory.
+    NotFound,
+    /// A rewriter decided the request should be redirected.
+    Redirect(Redirect),
+}
+
+/// The state threaded through the rewrite chain for a single request.
+///
+/// Carries the current path (relative to `root`), the embedded directory being
+/// served, and what that path currently [resolves](Resolved) to. Rewriters are
+/// free to rewrite the path and re-[`resolve`](FileResolution::resolve) it.
+pub struct FileResolution<'r> {
+    /// The embedded directory this `StaticFiles` serves.
+    pub root: &'r Dir<'r>,
+    /// The current path, relative to `root`.
+    pub path: PathBuf,
+    /// What `path` currently resolves to.
+    pub kind: Resolved<'r>,
+}
+
+impl<'r> FileResolution<'r> {
+    /// Resolve `path` against `root`, producing a [`File`], [`Dir`](Resolved::Dir)
+    /// or [`NotFound`](Resolved::NotFound) resolution.
+    pub fn resolve(root: &'r Dir<'r>, path: PathBuf) -> Self {
+        let kind = if path.as_os_str().is_empty() {
+            Resolved::Dir(root)
+        } else if let Some(dir) = root.get_dir(&path) {
+            Resolved::Dir(dir)
+        } else if let Some(file) = root.get_file(&path) {
+            Resolved::File(file)
+        } else {
+            Resolved::NotFound
+        };
+        FileResolution { root, path, kind }
+    }
+}
+
+/// A single step in a `StaticFiles` resolution pipeline.
+///
+/// The handler seeds the chain with the resolution of the raw request path and
+/// then hands each rewriter the output of the previous one. Returning `None`
+/// drops the request (it will be forwarded as `NotFound`); returning a new
+/// [`FileResolution`] replaces whatever was matched so far.
+pub trait Rewriter: Send + Sync + 'static {
+    /// Rewrite the current resolution, or drop it by returning `None`.
+    fn rewrite<'r>(
+        &self,
+        file: Option<FileResolution<'r>>,
+        req: &Request<'_>,
+    ) -> Option<FileResolution<'r>>;
+}
+
+/// Strip or prepend a leading path segment before re-resolving.
+///
+/// Analogous to upstream Rocket's `Prefix` rewriter.
+pub struct Prefix {
+    segment: PathBuf,
+    mode: PrefixMode,
+}
+
+enum PrefixMode {
+    Strip,
+    Prepend,
+}
+
+impl Prefix {
+    /// Strip `segment` from the front of the path, if present.
+    pub fn strip(segment: impl Into<PathBuf>) -> Self {
+        Self {
+            segment: segment.into(),
+            mode: PrefixMode::Strip,
+        }
+    }
+
+    /// Prepend `segment` to the front of the path.
+    pub fn prepend(segment: impl Into<PathBuf>) -> Self {
+        Self {
+            segment: segment.into(),
+            mode: PrefixMode::Prepend,
+        }
+    }
+}
+
+impl Rewriter for Prefix {
+    fn rewrite<'r>(
+        &self,
+        file: Option<FileResolution<'r>>,
+        _req: &Request<'_>,
+    ) -> Option<FileResolution<'r>> {
+        file.map(|res| {
+            let path = match self.mode {
+                PrefixMode::Strip => res
+                    .path
+                    .strip_prefix(&self.segment)
+                    .map(PathBuf::from)
+                    .unwrap_or(res.path),
+                PrefixMode::Prepend => self.segment.join(&res.path),
+            };
+            FileResolution::resolve(res.root, path)
+        })
+    }
+}
+
+/// Resolve a directory to one of its index files.
+///
+/// [`unconditional`](DirIndex::unconditional) always rewrites a directory to the
+/// named index (yielding `NotFound` if it is absent), while
+/// [`if_exists`](DirIndex::if_exists) only rewrites when the index is present,
+/// leaving the directory in place otherwise so further candidates can be tried.
+/// `Options::Index` (part of `Options::default()`) installs an unconditional
+/// `index.html` rewriter ahead of any added ones, so build the chain from
+/// `Options::None`:
+///
+/// ```rust
+/// # use rocket_include_dir::{DirIndex, StaticFiles, Dir, include_dir};
+/// # use rocket::fs::Options;
+/// # static PROJECT_DIR: Dir = include_dir!("static");
+/// StaticFiles::new(&PROJECT_DIR, Options::None)
+///     .rewrite(DirIndex::if_exists("index.html"))
+///     .rewrite(DirIndex::if_exists("index.htm"));
+/// ```
+pub struct DirIndex {
+    index: PathBuf,
+    conditional: bool,
+}
+
+impl DirIndex {
+    /// Always rewrite a directory to `index`, even when it does not exist.
+    pub fn unconditional(index: impl Into<PathBuf>) -> Self {
+        Self {
+            index: index.into(),
+            conditional: false,
+        }
+    }
+
+    /// Rewrite a directory to `index` only when that file exists.
+    pub fn if_exists(index: impl Into<PathBuf>) -> Self {
+        Self {
+            index: index.into(),
+            conditional: true,
+        }
+    }
+}
+
+impl Rewriter for DirIndex {
+    fn rewrite<'r>(
+        &self,
+        file: Option<FileResolution<'r>>,
+        _req: &Request<'_>,
+    ) -> Option<FileResolution<'r>> {
+        file.map(|res| match res.kind {
+            Resolved::Dir(dir) => {
+                let path = res.path.join(&self.index);
+                if let Some(file) = dir.get_file(&path) {
+                    FileResolution {
+                        root: res.root,
+                        path,
+                        kind: Resolved::File(file),
+                    }
+                } else if self.conditional {
+                    res
+                } else {
+                    FileResolution {
+                        root: res.root,
+                        path,
+                        kind: Resolved::NotFound,
+                    }
+                }
+            }
+            _ => res,
+        })
+    }
+}
+
+/// Redirect a directory request without a trailing slash to the slash-suffixed
+/// path, matching the `NormalizeDirs` behaviour.
+pub struct TrailingDirs;
+
+impl Rewriter for TrailingDirs {
+    fn rewrite<'r>(
+        &self,
+        file: Option<FileResolution<'r>>,
+        req: &Request<'_>,
+    ) -> Option<FileResolution<'r>> {
+        file.map(|res| match res.kind {
+            Resolved::Dir(_) if !req.uri().path().ends_with('/') => {
+                let normal = req
+                    .uri()
+                    .map_path(|p| format!("{}/", p))
+                    .expect("adding a trailing slash to a known good path => valid path")
+                    .into_owned();
+                FileResolution {
+                    root: res.root,
+                    path: res.path,
+                    kind: Resolved::Redirect(Redirect::permanent(normal)),
+                }
+            }
+            _ => res,
+        })
+    }
+}
+
+/// A [`Rewriter`] that drops a matched [`File`] unless a predicate accepts it.
+struct Filter<F>(F);
+
+impl<F> Rewriter for Filter<F>
+where
+    F: Fn(&File<'_>, &Request<'_>) -> bool + Send + Sync + 'static,
+{
+    fn rewrite<'r>(
+        &self,
+        file: Option<FileResolution<'r>>,
+        req: &Request<'_>,
+    ) -> Option<FileResolution<'r>> {
+        file.and_then(|res| match &res.kind {
+            Resolved::File(f) if !(self.0)(f, req) => None,
+            _ => Some(res),
+        })
+    }
+}
+
 /// Implements a simple bridge between `include_dir` and `rocket`. A simple reponder based on
 /// [`rocket::FileServer`], which uses a directory included at compile time.
 ///
@@ -45,20 +282,26 @@ pub use include_dir::Dir;
 /// # let response = client.get("/test.txt").dispatch();
 /// # assert_eq!(response.status(), Status::Ok);
 /// ```
-#[derive(Clone, Copy)]
+///
+/// Path resolution runs through a composable [`Rewriter`] pipeline. The coarse
+/// [`Options`] bitset passed to [`new`](StaticFiles::new) is translated into the
+/// equivalent standard rewriters ([`TrailingDirs`], [`DirIndex`]); further
+/// behaviour can be layered on with [`rewrite`](StaticFiles::rewrite) and
+/// [`filter`](StaticFiles::filter).
+#[derive(Clone)]
 pub struct StaticFiles {
     dir: &'static Dir<'static>,
     options: Options,
+    rewrites: Vec<Arc<dyn Rewriter>>,
     rank: isize,
+    content_types: ContentTypes,
+    #[cfg(feature = "compression")]
+    compression: Option<compression::Config>,
 }
 
 impl From<&'static Dir<'static>> for StaticFiles {
     fn from(dir: &'static Dir<'static>) -> Self {
-        Self {
-            dir,
-            options: Options::default(),
-            rank: Self::DEFAULT_RANK,
-        }
+        Self::new(dir, Options::default())
     }
 }
 
@@ -73,33 +316,384 @@ impl StaticFiles {
         Self {
             dir,
             options,
+            rewrites: Self::rewrites_for(options),
             rank: Self::DEFAULT_RANK,
+            content_types: ContentTypes::default(),
+            #[cfg(feature = "compression")]
+            compression: None,
         }
     }
 
+    /// The standard rewriters equivalent to an [`Options`] bitset.
+    fn rewrites_for(options: Options) -> Vec<Arc<dyn Rewriter>> {
+        let mut rewrites: Vec<Arc<dyn Rewriter>> = Vec::new();
+        if options.contains(Options::NormalizeDirs) {
+            rewrites.push(Arc::new(TrailingDirs));
+        }
+        if options.contains(Options::Index) {
+            rewrites.push(Arc::new(DirIndex::unconditional("index.html")));
+        }
+        rewrites
+    }
+
     /// Replace the options for this `StaticFiles`
+    ///
+    /// This resets the rewrite pipeline to the rewriters implied by `options`;
+    /// call it before layering on custom [`rewrite`](StaticFiles::rewrite)s.
     pub fn options(mut self, options: Options) -> Self {
         self.options = options;
+        self.rewrites = Self::rewrites_for(options);
+        self
+    }
+
+    /// Append a [`Rewriter`] to this `StaticFiles`' resolution pipeline.
+    pub fn rewrite(mut self, rewriter: impl Rewriter) -> Self {
+        self.rewrites.push(Arc::new(rewriter));
         self
     }
 
+    /// Drop any matched [`File`] for which `predicate` returns `false`.
+    pub fn filter(
+        self,
+        predicate: impl Fn(&File<'_>, &Request<'_>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.rewrite(Filter(predicate))
+    }
+
     /// Set a non-default rank for this `StaticFiles`
     pub fn rank(mut self, rank: isize) -> Self {
         self.rank = rank;
         self
     }
+
+    /// Resolve the `Content-Type` of a served file with a custom function.
+    ///
+    /// The resolver receives the file's path and its contents and is consulted
+    /// before the extension heuristic, so it can sniff magic numbers for
+    /// extensionless files or override the type of specific paths. Returning
+    /// `None` falls through to the extension heuristic and then to any
+    /// [`default_content_type`](StaticFiles::default_content_type).
+    pub fn content_type_by(
+        mut self,
+        resolver: impl Fn(&FsPath, &[u8]) -> Option<ContentType> + Send + Sync + 'static,
+    ) -> Self {
+        self.content_types.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Use `content_type` when neither the custom resolver nor the extension
+    /// heuristic yields a type, in place of Rocket's framework default.
+    pub fn default_content_type(mut self, content_type: ContentType) -> Self {
+        self.content_types.default = Some(content_type);
+        self
+    }
+
+    /// Serve compressed variants via `Accept-Encoding`
+    /// negotiation, enabling the given [`Encodings`](compression::Encodings).
+    ///
+    /// Each eligible file is compressed once on first access and cached;
+    /// already-compressed content types and files below a small threshold are
+    /// always served uncompressed, as is any client that doesn't advertise a
+    /// matching encoding.
+    #[cfg(feature = "compression")]
+    pub fn compressed(mut self, encodings: compression::Encodings) -> Self {
+        self.compression = Some(compression::Config::new(encodings));
+        self
+    }
+
+    /// The compression argument threaded into [`respond_with`].
+    fn compression(&self) -> CompressionArg<'_> {
+        #[cfg(feature = "compression")]
+        {
+            self.compression.as_ref()
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+        }
+    }
+}
+
+/// The `Cache-Control` policy emitted alongside every served file. Because the
+/// bytes are frozen at compile time, we let clients cache them but always
+/// revalidate against the strong `ETag`.
+const CACHE_CONTROL: &str = "public, max-age=0, must-revalidate";
+
+/// A strong `ETag` derived from a file's contents, computed once per file.
+///
+/// The bytes never change at runtime, so a content hash is a stable validator.
+/// Results are memoized in a process-wide map keyed by path and the address of
+/// the embedded bytes (so equal paths in different embedded directories do not
+/// share an entry), and each file is hashed at most once.
+fn etag_for(path: &FsPath, contents: &[u8]) -> String {
+    static CACHE: OnceLock<Mutex<HashMap<(PathBuf, usize), String>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (path.to_path_buf(), contents.as_ptr() as usize);
+    let mut map = cache.lock().expect("etag cache poisoned");
+    if let Some(etag) = map.get(&key) {
+        return etag.clone();
+    }
+    let etag = format!("\"{:016x}\"", fnv1a_64(contents));
+    map.insert(key, etag.clone());
+    etag
+}
+
+/// FNV-1a over 64 bits — a small, dependency-free content hash.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = OFFSET_BASIS;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Whether an `If-None-Match` header value matches `etag`, honouring `*` and
+/// weak (`W/`) comparison.
+fn etag_matches(header: &str, etag: &str) -> bool {
+    header.split(',').any(|candidate| {
+        let candidate = candidate.trim();
+        candidate == "*" || candidate.trim_start_matches("W/") == etag
+    })
+}
+
+/// Guess a [`ContentType`] for `path` from its extension.
+fn content_type_for(path: &FsPath) -> Option<ContentType> {
+    path.extension()
+        .and_then(|ext| ContentType::from_extension(&ext.to_string_lossy()))
+}
+
+/// A user-supplied content-type resolver: `(path, leading bytes) -> type`.
+type ContentTypeFn = dyn Fn(&FsPath, &[u8]) -> Option<ContentType> + Send + Sync;
+
+/// How a [`StaticFiles`] decides the `Content-Type` of a served file.
+///
+/// An optional resolver is consulted first (e.g. to sniff magic numbers for
+/// extensionless files), falling back to the extension heuristic and finally to
+/// an optional default.
+#[derive(Clone, Default)]
+struct ContentTypes {
+    resolver: Option<Arc<ContentTypeFn>>,
+    default: Option<ContentType>,
+}
+
+impl ContentTypes {
+    /// Resolve the content type for `path`, consulting the custom resolver, the
+    /// extension heuristic and the configured default, in that order.
+    fn resolve(&self, path: &FsPath, contents: &[u8]) -> Option<ContentType> {
+        if let Some(resolver) = &self.resolver {
+            if let Some(content_type) = resolver(path, contents) {
+                return Some(content_type);
+            }
+        }
+        content_type_for(path).or_else(|| self.default.clone())
+    }
+}
+
+/// The compression configuration threaded into [`respond_with`]. Without the
+/// `compression` feature there is nothing to thread, so it collapses to `()`.
+#[cfg(feature = "compression")]
+type CompressionArg<'a> = Option<&'a compression::Config>;
+#[cfg(not(feature = "compression"))]
+type CompressionArg<'a> = ();
+
+/// The outcome of parsing a `Range` header against a known total length.
+enum RangeSpec {
+    /// Serve the whole body (no usable range requested).
+    Full,
+    /// Serve the inclusive `start..=end` byte range.
+    Partial { start: u64, end: u64 },
+    /// The range cannot be satisfied; answer `416`.
+    Unsatisfiable,
+}
+
+/// Parse a single-range `Range` header value against a body of `total` bytes.
+///
+/// Handles `bytes=start-end`, open-ended `bytes=start-` and suffix
+/// `bytes=-n` forms. Unsupported units and multi-range requests fall back to
+/// serving the full body.
+fn parse_range(header: &str, total: u64) -> RangeSpec {
+    let spec = match header.strip_prefix("bytes=") {
+        Some(spec) => spec.trim(),
+        // Only the `bytes` unit is supported; ignore anything else.
+        None => return RangeSpec::Full,
+    };
+    // Multi-range responses are not supported; serve the full body instead.
+    if spec.contains(',') {
+        return RangeSpec::Full;
+    }
+    // An unparseable `Range` (no `-` separator) is ignored per RFC 9110
+    // §14.2: serve the full body rather than answering `416`.
+    let (start, end) = match spec.split_once('-') {
+        Some(parts) => parts,
+        None => return RangeSpec::Full,
+    };
+    match (start.trim(), end.trim()) {
+        // A bare `-` (`bytes=-`) is syntactically invalid; ignore it.
+        ("", "") => RangeSpec::Full,
+        // Suffix range: the last `n` bytes.
+        ("", suffix) => match suffix.parse::<u64>() {
+            // A zero-length suffix, or any suffix of empty content, is valid
+            // syntax but unsatisfiable.
+            Ok(0) => RangeSpec::Unsatisfiable,
+            Ok(_) if total == 0 => RangeSpec::Unsatisfiable,
+            Ok(n) => {
+                let n = n.min(total);
+                RangeSpec::Partial {
+                    start: total - n,
+                    end: total - 1,
+                }
+            }
+            // Not a number: ignore the header and serve the full body.
+            Err(_) => RangeSpec::Full,
+        },
+        // Open-ended range: from `start` to the end.
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) if start < total => RangeSpec::Partial {
+                start,
+                end: total - 1,
+            },
+            // Valid number past the end: unsatisfiable.
+            Ok(_) => RangeSpec::Unsatisfiable,
+            Err(_) => RangeSpec::Full,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end && start < total => RangeSpec::Partial {
+                start,
+                end: end.min(total - 1),
+            },
+            // Valid numbers that don't overlap the content: unsatisfiable.
+            (Ok(_), Ok(_)) => RangeSpec::Unsatisfiable,
+            // Non-numeric bounds: ignore the header and serve the full body.
+            _ => RangeSpec::Full,
+        },
+    }
 }
 
 fn respond_with<'r>(
     req: &'r Request<'_>,
     path: PathBuf,
     file: &'r File<'r>,
+    content_types: &ContentTypes,
+    compression: CompressionArg<'_>,
 ) -> response::Result<'r> {
-    let mut response = file.contents().respond_to(req)?;
-    if let Some(ext) = path.extension() {
-        if let Some(ct) = ContentType::from_extension(&ext.to_string_lossy()) {
-            response.set_header(ct);
+    #[cfg(not(feature = "compression"))]
+    let _ = compression;
+
+    // A resource that can vary by encoding must advertise `Vary: Accept-Encoding`
+    // on every representation — including the identity fallback and the 304/416
+    // paths — so shared caches key their stored entries correctly.
+    #[cfg(feature = "compression")]
+    let vary_encoding = compression.is_some();
+    #[cfg(not(feature = "compression"))]
+    let vary_encoding = false;
+
+    let contents = file.contents();
+    let base_etag = etag_for(&path, contents);
+
+    let total = contents.len() as u64;
+    let range = req.headers().get_one("Range").map(|h| parse_range(h, total));
+
+    // Negotiate the precompressed representation up front: which encoding (if
+    // any) we serve decides the ETag, and revalidation must compare against the
+    // ETag that will actually be sent. Range requests are served from the
+    // identity encoding, so only negotiate when no range was requested.
+    #[cfg(feature = "compression")]
+    let negotiated = if range.is_none() {
+        compression.and_then(|config| {
+            let content_type = content_types.resolve(&path, contents);
+            config
+                .negotiate(req, &path, contents, content_type.as_ref())
+                .map(|(buffer, encoding)| (buffer, encoding, content_type))
+        })
+    } else {
+        None
+    };
+
+    // The validator for the representation we will serve. A strong ETag must be
+    // unique per representation (RFC 9110 §8.8.1), so a compressed variant is
+    // tagged with its coding token.
+    #[cfg(feature = "compression")]
+    let etag = match &negotiated {
+        Some((_, encoding, _)) => format!("{}-{}\"", base_etag.trim_end_matches('"'), encoding),
+        None => base_etag,
+    };
+    #[cfg(not(feature = "compression"))]
+    let etag = base_etag;
+
+    // Short-circuit revalidation: if the client already holds the bytes of the
+    // representation we would serve, answer `304 Not Modified` empty.
+    if let Some(if_none_match) = req.headers().get_one("If-None-Match") {
+        if etag_matches(if_none_match, &etag) {
+            let mut builder = response::Response::build();
+            builder
+                .status(Status::NotModified)
+                .header(Header::new("ETag", etag))
+                .header(Header::new("Cache-Control", CACHE_CONTROL));
+            if vary_encoding {
+                builder.header(Header::new("Vary", "Accept-Encoding"));
+            }
+            return builder.ok();
+        }
+    }
+
+    if let Some(RangeSpec::Unsatisfiable) = range {
+        let mut builder = response::Response::build();
+        builder
+            .status(Status::RangeNotSatisfiable)
+            .header(Header::new("Content-Range", format!("bytes */{}", total)))
+            .header(Header::new("Accept-Ranges", "bytes"))
+            .header(Header::new("ETag", etag));
+        if vary_encoding {
+            builder.header(Header::new("Vary", "Accept-Encoding"));
+        }
+        return builder.ok();
+    }
+
+    // Serve the precompressed representation negotiated above.
+    #[cfg(feature = "compression")]
+    if let Some((buffer, encoding, content_type)) = negotiated {
+        let len = buffer.len();
+        let mut builder = response::Response::build();
+        builder
+            .sized_body(len, std::io::Cursor::new(buffer))
+            .header(Header::new("Content-Encoding", encoding))
+            .header(Header::new("Vary", "Accept-Encoding"))
+            .header(Header::new("ETag", etag))
+            .header(Header::new("Cache-Control", CACHE_CONTROL))
+            .header(Header::new("Accept-Ranges", "bytes"));
+        if let Some(content_type) = content_type {
+            builder.header(content_type);
         }
+        return builder.ok();
+    }
+
+    let partial = match range {
+        Some(RangeSpec::Partial { start, end }) => Some((start, end)),
+        _ => None,
+    };
+    let body = match partial {
+        Some((start, end)) => &contents[start as usize..=end as usize],
+        None => contents,
+    };
+
+    let mut response = body.respond_to(req)?;
+    if let Some(content_type) = content_types.resolve(&path, contents) {
+        response.set_header(content_type);
+    }
+    response.set_header(Header::new("ETag", etag));
+    response.set_header(Header::new("Cache-Control", CACHE_CONTROL));
+    response.set_header(Header::new("Accept-Ranges", "bytes"));
+    if vary_encoding {
+        response.set_header(Header::new("Vary", "Accept-Encoding"));
+    }
+    if let Some((start, end)) = partial {
+        response.set_status(Status::PartialContent);
+        response.set_header(Header::new(
+            "Content-Range",
+            format!("bytes {}-{}/{}", start, end, total),
+        ));
     }
 
     Ok(response)
@@ -108,68 +702,42 @@ fn respond_with<'r>(
 #[rocket::async_trait]
 impl Handler for StaticFiles {
     async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
-        // TODO: Should we reject dotfiles for `self.root` if !DotFiles?
-        let options = self.options;
         // Get the segments as a `PathBuf`, allowing dotfiles requested.
-        let allow_dotfiles = options.contains(Options::DotFiles);
+        let allow_dotfiles = self.options.contains(Options::DotFiles);
         let path = req
             .segments::<Segments<'_, Path>>(0..)
             .ok()
-            .and_then(|segments| segments.to_path_buf(allow_dotfiles).ok());
+            .and_then(|segments| segments.to_path_buf(allow_dotfiles).ok())
+            .unwrap_or_default();
 
-        match path {
-            Some(p) => {
-                // If the path is empty it means the root
-                let dir = if p.as_os_str().is_empty() {
-                    Some(self.dir)
-                } else {
-                    self.dir.get_dir(&p)
-                };
-                if let Some(path) = dir {
-                    if options.contains(Options::NormalizeDirs) && !req.uri().path().ends_with('/')
-                    {
-                        let normal = req
-                            .uri()
-                            .map_path(|p| format!("{}/", p))
-                            .expect("adding a trailing slash to a known good path => valid path")
-                            .into_owned();
-
-                        return Redirect::permanent(normal)
-                            .respond_to(req)
-                            .or_forward((data, Status::InternalServerError));
-                    }
-                    if !options.contains(Options::Index) {
-                        return Outcome::forward(data, Status::NotFound);
-                    }
-                    path.get_entry("index.html")
-                        .and_then(|f| f.as_file())
-                        .ok_or(Status::NotFound)
-                        .and_then(|path| respond_with(req, p.join("index.html"), path))
-                        .or_forward((data, Status::NotFound))
-                } else if let Some(path) = self.dir.get_file(&p) {
-                    respond_with(req, p, path).or_forward((data, Status::NotFound))
-                } else {
-                    Outcome::forward(data, Status::NotFound)
-                }
-            }
-            None => {
-                if options.contains(Options::Index) {
-                    self.dir.get_entry("index.html")
-                        .and_then(|f| f.as_file())
-                        .ok_or(Status::NotFound)
-                        .and_then(|path| respond_with(req, PathBuf::from("index.html"), path))
-                        .or_forward((data, Status::NotFound))
-                } else {
-                    Outcome::forward(data, Status::NotFound)
-                }
-            }
+        // Seed the pipeline with the raw path's resolution and walk the chain.
+        let mut resolution = Some(FileResolution::resolve(self.dir, path));
+        for rewriter in &self.rewrites {
+            resolution = rewriter.rewrite(resolution, req);
+        }
+
+        match resolution {
+            Some(FileResolution {
+                path,
+                kind: Resolved::File(file),
+                ..
+            }) => respond_with(req, path, file, &self.content_types, self.compression())
+                .or_forward((data, Status::NotFound)),
+            Some(FileResolution {
+                kind: Resolved::Redirect(redirect),
+                ..
+            }) => redirect
+                .respond_to(req)
+                .or_forward((data, Status::InternalServerError)),
+            _ => Outcome::forward(data, Status::NotFound),
         }
     }
 }
 
 impl From<StaticFiles> for Route {
     fn from(val: StaticFiles) -> Self {
-        Route::ranked(val.rank, Method::Get, "/<path..>", val)
+        let rank = val.rank;
+        Route::ranked(rank, Method::Get, "/<path..>", val)
     }
 }
 
@@ -179,6 +747,171 @@ impl From<StaticFiles> for Vec<Route> {
     }
 }
 
+/// Compressed serving (compressed on first access, then cached), gated behind
+/// the `compression` feature.
+#[cfg(feature = "compression")]
+mod compression {
+    use std::collections::HashMap;
+    use std::io::Write;
+    use std::path::{Path as FsPath, PathBuf};
+    use std::sync::{Mutex, OnceLock};
+
+    use rocket::http::ContentType;
+    use rocket::Request;
+
+    /// Files smaller than this (bytes) are not worth compressing.
+    const DEFAULT_MIN_SIZE: usize = 256;
+
+    /// Which precompressed encodings a [`StaticFiles`](super::StaticFiles) may
+    /// serve. Combine with `|`; brotli is always preferred when both are
+    /// enabled and accepted.
+    #[derive(Clone, Copy)]
+    pub struct Encodings {
+        pub(crate) brotli: bool,
+        pub(crate) gzip: bool,
+    }
+
+    impl Encodings {
+        /// Brotli only.
+        pub const BROTLI: Self = Self {
+            brotli: true,
+            gzip: false,
+        };
+        /// Gzip only.
+        pub const GZIP: Self = Self {
+            brotli: false,
+            gzip: true,
+        };
+        /// Both brotli and gzip.
+        pub const ALL: Self = Self {
+            brotli: true,
+            gzip: true,
+        };
+    }
+
+    impl std::ops::BitOr for Encodings {
+        type Output = Self;
+
+        fn bitor(self, rhs: Self) -> Self {
+            Self {
+                brotli: self.brotli || rhs.brotli,
+                gzip: self.gzip || rhs.gzip,
+            }
+        }
+    }
+
+    /// Per-`StaticFiles` compression configuration.
+    #[derive(Clone, Copy)]
+    pub(crate) struct Config {
+        encodings: Encodings,
+        min_size: usize,
+    }
+
+    impl Config {
+        pub(crate) fn new(encodings: Encodings) -> Self {
+            Self {
+                encodings,
+                min_size: DEFAULT_MIN_SIZE,
+            }
+        }
+
+        /// Negotiate an encoding for this request, returning the compressed
+        /// bytes and `Content-Encoding` token, or `None` to serve the identity
+        /// encoding.
+        pub(crate) fn negotiate(
+            &self,
+            req: &Request<'_>,
+            path: &FsPath,
+            contents: &[u8],
+            content_type: Option<&ContentType>,
+        ) -> Option<(Vec<u8>, &'static str)> {
+            if contents.len() < self.min_size || is_incompressible(content_type) {
+                return None;
+            }
+            let accept = req.headers().get_one("Accept-Encoding").unwrap_or("");
+            if self.encodings.brotli && accepts(accept, "br") {
+                return Some((compressed(path, "br", contents, brotli_compress), "br"));
+            }
+            if self.encodings.gzip && accepts(accept, "gzip") {
+                return Some((compressed(path, "gzip", contents, gzip_compress), "gzip"));
+            }
+            None
+        }
+    }
+
+    /// Whether `header` advertises support for the `token` coding. A coding
+    /// listed with `q=0` is refused, not supported.
+    fn accepts(header: &str, token: &str) -> bool {
+        header.split(',').any(|part| {
+            let mut params = part.split(';');
+            let coding = params.next().unwrap_or("").trim();
+            let refused = params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    == Some(0.0)
+            });
+            coding.eq_ignore_ascii_case(token) && !refused
+        })
+    }
+
+    /// Content types whose payloads are already compressed and gain nothing.
+    fn is_incompressible(content_type: Option<&ContentType>) -> bool {
+        match content_type {
+            Some(ct) => ct.top() == "image" || ct.is_zip() || ct.is_gzip(),
+            None => false,
+        }
+    }
+
+    /// Compress `contents` with `encoder` once, caching per (path, address of
+    /// the embedded bytes, encoding).
+    fn compressed(
+        path: &FsPath,
+        encoding: &str,
+        contents: &[u8],
+        encoder: fn(&[u8]) -> Vec<u8>,
+    ) -> Vec<u8> {
+        static CACHE: OnceLock<Mutex<HashMap<(PathBuf, usize, String), Vec<u8>>>> =
+            OnceLock::new();
+        let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let key = (
+            path.to_path_buf(),
+            contents.as_ptr() as usize,
+            encoding.to_owned(),
+        );
+        let mut map = cache.lock().expect("compression cache poisoned");
+        if let Some(buffer) = map.get(&key) {
+            return buffer.clone();
+        }
+        let buffer = encoder(contents);
+        map.insert(key, buffer.clone());
+        buffer
+    }
+
+    fn brotli_compress(contents: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+        writer
+            .write_all(contents)
+            .expect("in-memory brotli compression is infallible");
+        drop(writer);
+        out
+    }
+
+    fn gzip_compress(contents: &[u8]) -> Vec<u8> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(contents)
+            .expect("in-memory gzip compression is infallible");
+        encoder
+            .finish()
+            .expect("in-memory gzip finish is infallible")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use include_dir::include_dir;
@@ -219,4 +952,155 @@ mod tests {
         let response = client.get("/indexed/").dispatch();
         assert_eq!(response.status(), Status::Ok);
     }
+
+    #[test]
+    fn filter_rejects_matched_files() {
+        // Move current dir to avoid checking the local filesystem for path existience
+        std::env::set_current_dir("/tmp").expect("Requires /tmp directory");
+        static PROJECT_DIR: Dir = include_dir!("static");
+        let rocket = build().mount(
+            "/",
+            StaticFiles::from(&PROJECT_DIR).filter(|file, _| {
+                // Reject any file whose name begins with a dot.
+                !file
+                    .path()
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with('.'))
+                    .unwrap_or(false)
+            }),
+        );
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let response = client.get("/test.txt").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        // A file the predicate rejects is dropped and forwarded as `NotFound`.
+        let rocket = build().mount(
+            "/",
+            StaticFiles::from(&PROJECT_DIR).filter(|file, _| file.path() != FsPath::new("test.txt")),
+        );
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let response = client.get("/test.txt").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn conditional_get_returns_not_modified() {
+        // Move current dir to avoid checking the local filesystem for path existience
+        std::env::set_current_dir("/tmp").expect("Requires /tmp directory");
+        let client = Client::tracked(launch()).expect("valid rocket instance");
+
+        let response = client.get("/default/test.txt").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let etag = response
+            .headers()
+            .get_one("ETag")
+            .expect("ETag present on served files")
+            .to_owned();
+
+        let response = client
+            .get("/default/test.txt")
+            .header(Header::new("If-None-Match", etag))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.into_bytes(), None);
+    }
+
+    #[test]
+    fn range_request_returns_partial_content() {
+        // Move current dir to avoid checking the local filesystem for path existience
+        std::env::set_current_dir("/tmp").expect("Requires /tmp directory");
+        let client = Client::tracked(launch()).expect("valid rocket instance");
+
+        let full = client.get("/default/test.txt").dispatch();
+        assert_eq!(full.status(), Status::Ok);
+        assert_eq!(full.headers().get_one("Accept-Ranges"), Some("bytes"));
+        let total = full.into_bytes().expect("body present").len();
+
+        let response = client
+            .get("/default/test.txt")
+            .header(Header::new("Range", "bytes=0-0"))
+            .dispatch();
+        assert_eq!(response.status(), Status::PartialContent);
+        assert_eq!(
+            response.headers().get_one("Content-Range"),
+            Some(format!("bytes 0-0/{}", total).as_str())
+        );
+        assert_eq!(response.into_bytes().map(|b| b.len()), Some(1));
+
+        let response = client
+            .get("/default/test.txt")
+            .header(Header::new("Range", format!("bytes={}-", total)))
+            .dispatch();
+        assert_eq!(response.status(), Status::RangeNotSatisfiable);
+        assert_eq!(
+            response.headers().get_one("Content-Range"),
+            Some(format!("bytes */{}", total).as_str())
+        );
+
+        // An unparseable `Range` is ignored and the full body is served.
+        let response = client
+            .get("/default/test.txt")
+            .header(Header::new("Range", "bytes=abc"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_bytes().map(|b| b.len()), Some(total));
+    }
+
+    #[test]
+    fn default_content_type_for_extensionless_file() {
+        // Move current dir to avoid checking the local filesystem for path existience
+        std::env::set_current_dir("/tmp").expect("Requires /tmp directory");
+        static PROJECT_DIR: Dir = include_dir!("static");
+        let rocket = build().mount(
+            "/",
+            StaticFiles::from(&PROJECT_DIR).default_content_type(ContentType::Plain),
+        );
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let response = client.get("/inner/goodbye").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compressed_variant_negotiates_and_revalidates() {
+        // Move current dir to avoid checking the local filesystem for path existience
+        std::env::set_current_dir("/tmp").expect("Requires /tmp directory");
+        static PROJECT_DIR: Dir = include_dir!("static");
+        let rocket = build().mount(
+            "/",
+            StaticFiles::from(&PROJECT_DIR).compressed(Encodings::GZIP),
+        );
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .get("/test.txt")
+            .header(Header::new("Accept-Encoding", "gzip"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+        assert_eq!(
+            response.headers().get_one("Vary"),
+            Some("Accept-Encoding")
+        );
+        let etag = response
+            .headers()
+            .get_one("ETag")
+            .expect("ETag present on compressed files")
+            .to_owned();
+        // The strong ETag is tagged with the coding token, distinct from the
+        // identity representation.
+        assert!(etag.ends_with("-gzip\""), "unexpected ETag: {etag}");
+
+        // Revalidating the gzip variant with its own ETag must yield 304, not a
+        // fresh 200 with the whole compressed body.
+        let response = client
+            .get("/test.txt")
+            .header(Header::new("Accept-Encoding", "gzip"))
+            .header(Header::new("If-None-Match", etag))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.into_bytes(), None);
+    }
 }